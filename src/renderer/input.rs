@@ -1,15 +1,29 @@
 //! This module contains functions related to processing input events.
 
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::mem::size_of;
 use std::sync::mpsc;
 
-use imgui::Io;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use imgui::{ClipboardBackend, ConfigFlags, Io, NavInput};
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard, SetClipboardData,
+};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE, HGLOBAL};
+use windows::Win32::System::Ole::CF_UNICODETEXT;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use windows::Win32::UI::Input::XboxController::{
+    XInputGetState, XINPUT_GAMEPAD_A, XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_BACK,
+    XINPUT_GAMEPAD_DPAD_DOWN, XINPUT_GAMEPAD_DPAD_LEFT, XINPUT_GAMEPAD_DPAD_RIGHT,
+    XINPUT_GAMEPAD_DPAD_UP, XINPUT_GAMEPAD_LEFT_SHOULDER, XINPUT_GAMEPAD_LEFT_THUMB_DEADZONE,
+    XINPUT_GAMEPAD_RIGHT_SHOULDER, XINPUT_GAMEPAD_START, XINPUT_GAMEPAD_TRIGGER_THRESHOLD,
+    XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XINPUT_STATE,
+};
 use windows::Win32::UI::Input::{
-    GetRawInputData, HRAWINPUT, RAWINPUT, RAWINPUTHEADER, RAWKEYBOARD, RAWMOUSE_0_0,
-    RID_DEVICE_INFO_TYPE, RID_INPUT, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE,
+    GetRawInputData, RegisterRawInputDevices, HRAWINPUT, RAWHID, RAWINPUT, RAWINPUTDEVICE,
+    RAWINPUTHEADER, RAWKEYBOARD, RAWMOUSE, RAWINPUT_DEVICE_FLAGS, RID_DEVICE_INFO_TYPE, RID_INPUT,
+    RIDEV_INPUTSINK, RIM_TYPEHID, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE,
 };
 use windows::Win32::UI::WindowsAndMessaging::*;
 
@@ -24,12 +38,37 @@ fn hiword(l: u32) -> u16 {
     ((l >> 16) & 0xffff) as u16
 }
 
+// Replication of the Win32 LOWORD macro.
+#[inline]
+fn loword(l: u32) -> u16 {
+    (l & 0xffff) as u16
+}
+
+// Replication of the Win32 GET_X_LPARAM macro. The low word is sign-extended,
+// since screen coordinates can be negative on multi-monitor setups.
+#[inline]
+fn get_x_lparam(lparam: isize) -> f32 {
+    loword(lparam as u32) as i16 as f32
+}
+
+// Replication of the Win32 GET_Y_LPARAM macro.
+#[inline]
+fn get_y_lparam(lparam: isize) -> f32 {
+    hiword(lparam as u32) as i16 as f32
+}
+
 pub(crate) enum InputChange {
+    MouseMove { x: f32, y: f32 },
     MouseDown { index: usize, value: bool },
     KeyDown { index: usize, value: bool },
     MouseWheelScroll { delta: f32 },
     MouseWheelHorizontalScroll { delta: f32 },
     AddInputCharacter { character: char },
+    // A single variable-length report from a generic HID device (joysticks,
+    // flight sticks, steering wheels...). Unlike the other variants this has
+    // no standard ImGui IO slot to land in; it's surfaced so hosts can bind
+    // the raw bytes to their own overlay actions.
+    HidInput { report: Vec<u8> },
     CtrlPressed { value: bool },
     ShiftPressed { value: bool },
     AltPressed { value: bool },
@@ -40,13 +79,90 @@ pub(crate) enum InputChange {
 // Raw input
 ////////////////////////////////////////////////////////////////////////////////
 
+// `RAWMOUSE.usFlags` value indicating the reported position is absolute
+// (e.g. tablets, remote desktop) rather than a relative delta.
+const MOUSE_MOVE_ABSOLUTE: u32 = 0x01;
+// `RAWMOUSE.usFlags` value indicating an absolute position is expressed
+// across the virtual desktop (all monitors) rather than the primary screen.
+const MOUSE_VIRTUAL_DESKTOP: u32 = 0x02;
+
+// "Generic Desktop Controls", the usage page covering every usage
+// registered below.
+const USAGE_PAGE_GENERIC: u16 = 0x01;
+const USAGE_MOUSE: u16 = 0x02;
+const USAGE_KEYBOARD: u16 = 0x06;
+// Joystick, gamepad, and multi-axis controller usages, registered so
+// `handle_raw_hid_input` actually receives `RIM_TYPEHID` reports from these
+// generic controllers (flight sticks, steering wheels, and the like).
+const USAGE_JOYSTICK: u16 = 0x04;
+const USAGE_GAMEPAD: u16 = 0x05;
+const USAGE_MULTI_AXIS_CONTROLLER: u16 = 0x08;
+
+// Configures how raw input devices are registered for a window. Background
+// capture is opt-in since it isn't desirable for every host.
+#[derive(Default)]
+pub struct RawInputConfig {
+    capture_input_when_unfocused: bool,
+}
+
+impl RawInputConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Register devices with `RIDEV_INPUTSINK`, so `WM_INPUT` keeps arriving
+    // (as `RIM_INPUTSINK`, handled by `handle_raw_input`) even while the
+    // target window isn't in the foreground. Useful for hotkeys and menu
+    // toggles that should work whether or not the overlay has focus.
+    pub fn capture_input_when_unfocused(mut self, value: bool) -> Self {
+        self.capture_input_when_unfocused = value;
+        self
+    }
+}
+
+// Register `hwnd` to receive raw mouse, keyboard, and generic HID (joystick,
+// gamepad, multi-axis controller) input. Must be called once during window
+// setup; without it, `WM_INPUT` never arrives and `handle_raw_input`/
+// `handle_raw_hid_input` are unreachable.
+pub fn register_raw_input_devices(
+    hwnd: HWND,
+    config: &RawInputConfig,
+) -> windows::core::Result<()> {
+    let flags = if config.capture_input_when_unfocused {
+        RIDEV_INPUTSINK
+    } else {
+        RAWINPUT_DEVICE_FLAGS(0)
+    };
+
+    let device = |usUsage| RAWINPUTDEVICE {
+        usUsagePage: USAGE_PAGE_GENERIC,
+        usUsage,
+        dwFlags: flags,
+        hwndTarget: hwnd,
+    };
+    let devices = [
+        device(USAGE_MOUSE),
+        device(USAGE_KEYBOARD),
+        device(USAGE_JOYSTICK),
+        device(USAGE_GAMEPAD),
+        device(USAGE_MULTI_AXIS_CONTROLLER),
+    ];
+
+    unsafe { RegisterRawInputDevices(&devices, size_of::<RAWINPUTDEVICE>() as u32) }
+}
+
 // Handle raw mouse input events.
 //
 // Given the RAWINPUT structure, check each possible mouse flag status and
 // update the Io object accordingly. Both the key_down indices associated to the
 // mouse click (VK_...) and the values in mouse_down are updated.
-fn handle_raw_mouse_input(wnd_proc_tx: &mpsc::Sender<InputChange>, raw_mouse: &RAWMOUSE_0_0) {
-    let button_flags = raw_mouse.usButtonFlags as u32;
+fn handle_raw_mouse_input(
+    wnd_proc_tx: &mpsc::Sender<InputChange>,
+    hwnd: HWND,
+    raw_mouse: &RAWMOUSE,
+) {
+    let button = unsafe { &raw_mouse.Anonymous.Anonymous };
+    let button_flags = button.usButtonFlags as u32;
 
     let has_flag = |flag| button_flags & flag != 0;
     let set_key_down = |VIRTUAL_KEY(index), val| {
@@ -97,15 +213,56 @@ fn handle_raw_mouse_input(wnd_proc_tx: &mpsc::Sender<InputChange>, raw_mouse: &R
 
     // Apply vertical mouse scroll.
     if button_flags & RI_MOUSE_WHEEL != 0 {
-        let wheel_delta = raw_mouse.usButtonData as i16 / WHEEL_DELTA as i16;
+        let wheel_delta = button.usButtonData as i16 / WHEEL_DELTA as i16;
         _ = wnd_proc_tx.send(InputChange::MouseWheelScroll { delta: wheel_delta as f32 });
     }
 
     // Apply horizontal mouse scroll.
     if button_flags & RI_MOUSE_HWHEEL != 0 {
-        let wheel_delta = raw_mouse.usButtonData as i16 / WHEEL_DELTA as i16;
+        let wheel_delta = button.usButtonData as i16 / WHEEL_DELTA as i16;
         _ = wnd_proc_tx.send(InputChange::MouseWheelHorizontalScroll { delta: wheel_delta as f32 });
     }
+
+    // Ordinary relative motion (the common case for a plain mouse) is
+    // already delivered as an absolute, client-relative position by the
+    // legacy `WM_MOUSEMOVE` message. Raw input devices aren't registered
+    // with `RIDEV_NOLEGACY`, so both messages arrive for the same physical
+    // move; forwarding the relative delta here too would pile it on top of
+    // the already-correct `WM_MOUSEMOVE` position and make the cursor drift.
+    // Only absolute-position devices (tablets, remote desktop sessions) are
+    // handled here, since `WM_MOUSEMOVE` has no way to report those.
+    if raw_mouse.usFlags as u32 & MOUSE_MOVE_ABSOLUTE != 0 {
+        // Absolute coordinates come in as a value in the 0..65535 range
+        // spanning the screen — or, when `MOUSE_VIRTUAL_DESKTOP` is also
+        // set, the whole virtual desktop spanning every monitor. Scale
+        // against that rect to get back to screen space, then subtract the
+        // window's client-area origin (not its outer bounds, which include
+        // the title bar and borders) to land in the client-relative
+        // coordinates ImGui expects.
+        let virtual_desktop = raw_mouse.usFlags as u32 & MOUSE_VIRTUAL_DESKTOP != 0;
+        let (origin_x, origin_y, width, height) = unsafe {
+            if virtual_desktop {
+                (
+                    GetSystemMetrics(SM_XVIRTUALSCREEN),
+                    GetSystemMetrics(SM_YVIRTUALSCREEN),
+                    GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                    GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                )
+            } else {
+                (0, 0, GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN))
+            }
+        };
+
+        let screen_x = origin_x as f32 + (raw_mouse.lLastX as f32 / 65535.0) * width as f32;
+        let screen_y = origin_y as f32 + (raw_mouse.lLastY as f32 / 65535.0) * height as f32;
+
+        let mut client_origin = POINT::default();
+        if unsafe { ClientToScreen(hwnd, &mut client_origin) }.is_ok() {
+            let x = screen_x - client_origin.x as f32;
+            let y = screen_y - client_origin.y as f32;
+            _ = wnd_proc_tx.send(InputChange::MouseMove { x, y });
+        }
+    }
 }
 
 // Handle raw keyboard input.
@@ -159,21 +316,52 @@ fn handle_raw_keyboard_input(wnd_proc_tx: &mpsc::Sender<InputChange>, raw_keyboa
 }
 
 // Handle WM_INPUT events.
+//
+// `capture_input_when_unfocused` accepts `RIM_INPUTSINK` alongside the normal
+// `RIM_INPUT`, so hotkeys and menu toggles keep working while the target
+// window isn't in the foreground. This only has an effect if the devices
+// were themselves registered via `register_raw_input_devices` with
+// `RawInputConfig::capture_input_when_unfocused(true)`; pass the same value
+// used there so the two stay in sync.
 fn handle_raw_input(
     wnd_proc_tx: &mpsc::Sender<InputChange>,
+    hwnd: HWND,
+    capture_input_when_unfocused: bool,
     WPARAM(wparam): WPARAM,
     LPARAM(lparam): LPARAM,
 ) {
-    let mut raw_data = RAWINPUT { ..Default::default() };
-    let mut raw_data_size = size_of::<RAWINPUT>() as u32;
     let raw_data_header_size = size_of::<RAWINPUTHEADER>() as u32;
 
-    // Read the raw input data.
+    // Query the size of the data first instead of reading straight into a
+    // fixed-size `RAWINPUT`: generic HID reports (joysticks, wheels, flight
+    // sticks...) are variable-length and can exceed `size_of::<RAWINPUT>()`,
+    // so a heap buffer sized to the reported length is needed to read them
+    // without truncation.
+    let mut raw_data_size = 0u32;
+    let size_query = unsafe {
+        GetRawInputData(
+            HRAWINPUT(lparam),
+            RID_INPUT,
+            None,
+            &mut raw_data_size,
+            raw_data_header_size,
+        )
+    };
+    if size_query == u32::MAX || raw_data_size == 0 {
+        return;
+    }
+
+    // A `Vec<u8>` is only byte-aligned, but `RAWINPUTHEADER` has 8-byte
+    // aligned fields on x64 — reinterpreting a byte buffer as `*const
+    // RAWINPUT` would be undefined behavior. Allocate in `u64` units instead
+    // so the buffer's alignment matches what we read it back as.
+    let word_count = (raw_data_size as usize + size_of::<u64>() - 1) / size_of::<u64>();
+    let mut raw_data_buf = vec![0u64; word_count];
     let r = unsafe {
         GetRawInputData(
             HRAWINPUT(lparam),
             RID_INPUT,
-            Some(&mut raw_data as *mut _ as *mut c_void),
+            Some(raw_data_buf.as_mut_ptr() as *mut c_void),
             &mut raw_data_size,
             raw_data_header_size,
         )
@@ -184,29 +372,91 @@ fn handle_raw_input(
         return;
     }
 
-    // Ignore messages when window is not focused.
-    if (wparam as u32 & 0xFFu32) != RIM_INPUT {
+    let raw_data = unsafe { &*(raw_data_buf.as_ptr() as *const RAWINPUT) };
+
+    // Ignore messages when the window is not focused, unless background
+    // capture is enabled, in which case sink messages are accepted too.
+    let rim_type = wparam as u32 & 0xFFu32;
+    let accepted = rim_type == RIM_INPUT
+        || (capture_input_when_unfocused && rim_type == RIM_INPUTSINK);
+    if !accepted {
         return;
     }
 
     // Dispatch to the appropriate raw input processing method.
     match RID_DEVICE_INFO_TYPE(raw_data.header.dwType) {
         RIM_TYPEMOUSE => {
-            handle_raw_mouse_input(wnd_proc_tx, unsafe {
-                &raw_data.data.mouse.Anonymous.Anonymous
-            });
+            handle_raw_mouse_input(wnd_proc_tx, hwnd, unsafe { &raw_data.data.mouse });
         },
         RIM_TYPEKEYBOARD => {
             handle_raw_keyboard_input(wnd_proc_tx, unsafe { &raw_data.data.keyboard });
         },
+        RIM_TYPEHID => {
+            handle_raw_hid_input(wnd_proc_tx, unsafe { &raw_data.data.hid });
+        },
         _ => {},
     }
 }
 
+// Handle raw input from generic HID devices (joysticks, flight sticks,
+// steering wheels, multi-axis controllers) that don't fit the mouse/keyboard
+// model. `RAWHID` reports are packed back-to-back right after its header
+// fields, so `bRawData` (a one-byte placeholder in the binding) is only the
+// start of `dwCount` reports of `dwSizeHid` bytes each.
+//
+// For these reports to arrive at all, `register_raw_input_devices` must have
+// been called for `hwnd`, which registers the joystick (0x04), gamepad (0x05)
+// and multi-axis controller (0x08) usages of the generic desktop usage page
+// alongside mouse and keyboard.
+fn handle_raw_hid_input(wnd_proc_tx: &mpsc::Sender<InputChange>, raw_hid: &RAWHID) {
+    let report_size = raw_hid.dwSizeHid as usize;
+    let report_count = raw_hid.dwCount as usize;
+    if report_size == 0 || report_count == 0 {
+        return;
+    }
+
+    let reports = raw_hid.bRawData.as_ptr();
+    for i in 0..report_count {
+        let report =
+            unsafe { std::slice::from_raw_parts(reports.add(i * report_size), report_size) };
+        _ = wnd_proc_tx.send(InputChange::HidInput { report: report.to_vec() });
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Regular input
 ////////////////////////////////////////////////////////////////////////////////
 
+thread_local! {
+    // `WM_CHAR` delivers UTF-16 code units one at a time, so a high surrogate
+    // has to be held onto until its matching low surrogate arrives. The
+    // window procedure only ever runs on the thread that owns the window, so
+    // thread-local storage is enough to carry this across messages.
+    static PENDING_HIGH_SURROGATE: Cell<Option<u16>> = Cell::new(None);
+}
+
+// Decode a single UTF-16 code unit delivered by `WM_CHAR` into a `char`,
+// combining surrogate pairs across calls. Returns `None` while a high
+// surrogate is still waiting for its low surrogate, and drops lone or
+// invalid surrogates instead of producing replacement characters.
+fn decode_utf16_char(code_unit: u16) -> Option<char> {
+    PENDING_HIGH_SURROGATE.with(|pending| match code_unit {
+        0xD800..=0xDBFF => {
+            pending.set(Some(code_unit));
+            None
+        },
+        0xDC00..=0xDFFF => {
+            let high = pending.take()?;
+            let combined = 0x10000 + ((high as u32 - 0xD800) << 10) + (code_unit as u32 - 0xDC00);
+            char::from_u32(combined)
+        },
+        _ => {
+            pending.set(None);
+            char::from_u32(code_unit as u32)
+        },
+    })
+}
+
 fn map_vkey(wparam: u16, lparam: usize) -> VIRTUAL_KEY {
     match VIRTUAL_KEY(wparam) {
         VK_SHIFT => unsafe {
@@ -265,11 +515,15 @@ fn handle_input(
 
 pub(crate) fn update_io(io: &mut Io, input_change: InputChange) {
     match input_change {
+        InputChange::MouseMove { x, y } => io.mouse_pos = [x, y],
         InputChange::MouseDown { index, value } => io.mouse_down[index] = value,
         InputChange::KeyDown { index, value } => io.keys_down[index] = value,
         InputChange::MouseWheelScroll { delta } => io.mouse_wheel += delta,
         InputChange::MouseWheelHorizontalScroll { delta } => io.mouse_wheel_h += delta,
         InputChange::AddInputCharacter { character } => io.add_input_character(character),
+        // HID reports have no standard ImGui IO slot; they're meant to be
+        // intercepted upstream of this by hosts that bind custom controllers.
+        InputChange::HidInput { .. } => {},
         InputChange::CtrlPressed { value } => io.key_ctrl = value,
         InputChange::ShiftPressed { value } => io.key_shift = value,
         InputChange::AltPressed { value } => io.key_alt = value,
@@ -277,6 +531,152 @@ pub(crate) fn update_io(io: &mut Io, input_change: InputChange) {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Clipboard
+////////////////////////////////////////////////////////////////////////////////
+
+// Read the clipboard's `CF_UNICODETEXT` contents into a UTF-8 `String`.
+fn get_clipboard_text(hwnd: HWND) -> Option<String> {
+    unsafe {
+        OpenClipboard(hwnd).ok()?;
+
+        let text = (|| {
+            let handle = GetClipboardData(CF_UNICODETEXT.0 as u32).ok()?;
+            let hglobal = HGLOBAL(handle.0 as *mut _);
+            let ptr = GlobalLock(hglobal) as *const u16;
+            if ptr.is_null() {
+                return None;
+            }
+
+            let mut len = 0usize;
+            while *ptr.add(len) != 0 {
+                len += 1;
+            }
+            let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+            _ = GlobalUnlock(hglobal);
+            Some(text)
+        })();
+
+        _ = CloseClipboard();
+        text
+    }
+}
+
+// Encode `text` as UTF-16 and hand it to the clipboard as `CF_UNICODETEXT`.
+fn set_clipboard_text(hwnd: HWND, text: &str) {
+    unsafe {
+        if OpenClipboard(hwnd).is_err() {
+            return;
+        }
+
+        let utf16 = text.encode_utf16().chain(std::iter::once(0)).collect::<Vec<_>>();
+        let size = utf16.len() * size_of::<u16>();
+
+        if let Ok(hglobal) = GlobalAlloc(GMEM_MOVEABLE, size) {
+            let ptr = GlobalLock(hglobal) as *mut u16;
+            if !ptr.is_null() {
+                ptr.copy_from_nonoverlapping(utf16.as_ptr(), utf16.len());
+                _ = GlobalUnlock(hglobal);
+                _ = EmptyClipboard();
+                _ = SetClipboardData(CF_UNICODETEXT.0 as u32, HANDLE(hglobal.0 as isize));
+            }
+        }
+
+        _ = CloseClipboard();
+    }
+}
+
+// Backs ImGui's clipboard get/set callbacks with the Win32 clipboard, so
+// Ctrl+C/Ctrl+V inside overlay text widgets round-trip through the OS
+// clipboard rather than silently doing nothing.
+pub(crate) struct Win32ClipboardBackend {
+    hwnd: HWND,
+}
+
+impl Win32ClipboardBackend {
+    pub(crate) fn new(hwnd: HWND) -> Self {
+        Self { hwnd }
+    }
+}
+
+impl ClipboardBackend for Win32ClipboardBackend {
+    fn get(&mut self) -> Option<String> {
+        get_clipboard_text(self.hwnd)
+    }
+
+    fn set(&mut self, value: &str) {
+        set_clipboard_text(self.hwnd, value);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Gamepad
+////////////////////////////////////////////////////////////////////////////////
+
+// Normalize a raw thumbstick axis into the 0..1 range ImGui expects, clamping
+// anything inside `deadzone` to zero rather than letting stick drift register
+// as input.
+fn normalize_thumb_axis(value: i16, deadzone: i16) -> f32 {
+    let magnitude = value.unsigned_abs();
+    if magnitude <= deadzone as u16 {
+        return 0.0;
+    }
+
+    ((magnitude - deadzone as u16) as f32 / (i16::MAX as f32 - deadzone as f32)).min(1.0)
+}
+
+// Poll the first connected XInput controller, once per frame, and feed its
+// state into ImGui's gamepad navigation inputs. Enables
+// `NAV_ENABLE_GAMEPAD` automatically as soon as a controller responds, so
+// overlays can be driven entirely from a pad when no keyboard/mouse is handy.
+pub(crate) fn poll_gamepad_navigation(io: &mut Io) {
+    let mut state = XINPUT_STATE::default();
+    let Some(state) = (0..4).find_map(|user_index| {
+        (unsafe { XInputGetState(user_index, &mut state) } == 0).then_some(state)
+    }) else {
+        return;
+    };
+
+    io.config_flags.insert(ConfigFlags::NAV_ENABLE_GAMEPAD);
+
+    let gamepad = state.Gamepad;
+    let buttons = gamepad.wButtons;
+    let mut set = |nav_input: NavInput, value: f32| io.nav_inputs[nav_input as usize] = value;
+    let button = |flag: u16| if buttons.0 & flag != 0 { 1.0 } else { 0.0 };
+    let trigger = |value: u8| {
+        if value as u16 > XINPUT_GAMEPAD_TRIGGER_THRESHOLD.0 {
+            value as f32 / u8::MAX as f32
+        } else {
+            0.0
+        }
+    };
+
+    set(NavInput::Activate, button(XINPUT_GAMEPAD_A.0));
+    set(NavInput::Cancel, button(XINPUT_GAMEPAD_B.0));
+    set(NavInput::Menu, button(XINPUT_GAMEPAD_X.0));
+    set(NavInput::Input, button(XINPUT_GAMEPAD_Y.0).max(button(XINPUT_GAMEPAD_START.0)));
+    set(NavInput::DpadLeft, button(XINPUT_GAMEPAD_DPAD_LEFT.0));
+    set(NavInput::DpadRight, button(XINPUT_GAMEPAD_DPAD_RIGHT.0));
+    set(NavInput::DpadUp, button(XINPUT_GAMEPAD_DPAD_UP.0));
+    set(NavInput::DpadDown, button(XINPUT_GAMEPAD_DPAD_DOWN.0));
+    set(
+        NavInput::FocusPrev,
+        button(XINPUT_GAMEPAD_LEFT_SHOULDER.0).max(button(XINPUT_GAMEPAD_BACK.0)),
+    );
+    set(NavInput::FocusNext, button(XINPUT_GAMEPAD_RIGHT_SHOULDER.0));
+    set(NavInput::TweakSlow, trigger(gamepad.bLeftTrigger));
+    set(NavInput::TweakFast, trigger(gamepad.bRightTrigger));
+
+    let left_x =
+        normalize_thumb_axis(gamepad.sThumbLX, XINPUT_GAMEPAD_LEFT_THUMB_DEADZONE.0 as i16);
+    let left_y =
+        normalize_thumb_axis(gamepad.sThumbLY, XINPUT_GAMEPAD_LEFT_THUMB_DEADZONE.0 as i16);
+    set(NavInput::LStickLeft, if gamepad.sThumbLX < 0 { left_x } else { 0.0 });
+    set(NavInput::LStickRight, if gamepad.sThumbLX > 0 { left_x } else { 0.0 });
+    set(NavInput::LStickDown, if gamepad.sThumbLY < 0 { left_y } else { 0.0 });
+    set(NavInput::LStickUp, if gamepad.sThumbLY > 0 { left_y } else { 0.0 });
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Window procedure
 ////////////////////////////////////////////////////////////////////////////////
@@ -290,9 +690,16 @@ pub fn imgui_wnd_proc_impl(
     wnd_proc: WndProcType,
     wnd_proc_tx: &mpsc::Sender<InputChange>,
     should_block_messages: bool,
+    capture_input_when_unfocused: bool,
 ) -> LRESULT {
     match umsg {
-        WM_INPUT => handle_raw_input(wnd_proc_tx, WPARAM(wparam), LPARAM(lparam)),
+        WM_INPUT => handle_raw_input(
+            wnd_proc_tx,
+            hwnd,
+            capture_input_when_unfocused,
+            WPARAM(wparam),
+            LPARAM(lparam),
+        ),
         state @ (WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP) if wparam < 256 => {
             handle_input(wnd_proc_tx, state, WPARAM(wparam), LPARAM(lparam))
         },
@@ -322,6 +729,12 @@ pub fn imgui_wnd_proc_impl(
             let btn = if hiword(wparam as _) == XBUTTON1 { 3 } else { 4 };
             _ = wnd_proc_tx.send(InputChange::MouseDown { index: btn, value: false });
         },
+        WM_MOUSEMOVE => {
+            _ = wnd_proc_tx.send(InputChange::MouseMove {
+                x: get_x_lparam(lparam),
+                y: get_y_lparam(lparam),
+            });
+        },
         WM_MOUSEWHEEL => {
             // This `hiword` call is equivalent to GET_WHEEL_DELTA_WPARAM
             let wheel_delta_wparam = hiword(wparam as _);
@@ -339,8 +752,9 @@ pub fn imgui_wnd_proc_impl(
             });
         },
         WM_CHAR => {
-            _ = wnd_proc_tx
-                .send(InputChange::AddInputCharacter { character: wparam as u8 as char });
+            if let Some(character) = decode_utf16_char(wparam as u16) {
+                _ = wnd_proc_tx.send(InputChange::AddInputCharacter { character });
+            }
         },
         WM_SIZE => {
             RenderState::resize();
@@ -355,3 +769,71 @@ pub fn imgui_wnd_proc_impl(
 
     unsafe { CallWindowProcW(Some(wnd_proc), hwnd, umsg, WPARAM(wparam), LPARAM(lparam)) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_x_y_lparam_decode_positive_coordinates() {
+        let lparam = (100i16 as u16 as isize) | ((200i16 as u16 as isize) << 16);
+        assert_eq!(get_x_lparam(lparam), 100.0);
+        assert_eq!(get_y_lparam(lparam), 200.0);
+    }
+
+    #[test]
+    fn get_x_y_lparam_sign_extend_negative_coordinates() {
+        // Multi-monitor setups with a monitor to the left of/above the
+        // primary can report negative screen coordinates.
+        let lparam = (-10i16 as u16 as isize) | ((-20i16 as u16 as isize) << 16);
+        assert_eq!(get_x_lparam(lparam), -10.0);
+        assert_eq!(get_y_lparam(lparam), -20.0);
+    }
+
+    #[test]
+    fn decode_utf16_char_decodes_bmp_code_unit_directly() {
+        assert_eq!(decode_utf16_char('A' as u16), Some('A'));
+    }
+
+    #[test]
+    fn decode_utf16_char_combines_surrogate_pair() {
+        // U+1F600 GRINNING FACE, encoded as the surrogate pair 0xD83D 0xDE00.
+        assert_eq!(decode_utf16_char(0xD83D), None);
+        assert_eq!(decode_utf16_char(0xDE00), Some('\u{1F600}'));
+    }
+
+    #[test]
+    fn decode_utf16_char_drops_lone_low_surrogate() {
+        assert_eq!(decode_utf16_char(0xDE00), None);
+    }
+
+    #[test]
+    fn decode_utf16_char_drops_high_surrogate_followed_by_bmp_char() {
+        assert_eq!(decode_utf16_char(0xD83D), None);
+        // A second high surrogate (or any non-low-surrogate) cancels the
+        // pending one instead of combining with it.
+        assert_eq!(decode_utf16_char('A' as u16), Some('A'));
+    }
+
+    #[test]
+    fn normalize_thumb_axis_clamps_inside_deadzone_to_zero() {
+        assert_eq!(normalize_thumb_axis(100, 200), 0.0);
+        assert_eq!(normalize_thumb_axis(-100, 200), 0.0);
+        assert_eq!(normalize_thumb_axis(200, 200), 0.0);
+    }
+
+    #[test]
+    fn normalize_thumb_axis_scales_outside_deadzone() {
+        let deadzone = 100;
+        let value = i16::MAX;
+        assert_eq!(normalize_thumb_axis(value, deadzone), 1.0);
+        assert!(normalize_thumb_axis(200, deadzone) > 0.0);
+    }
+
+    #[test]
+    fn normalize_thumb_axis_handles_i16_min_magnitude() {
+        // `i16::MIN.unsigned_abs()` is 32768, which doesn't fit in `i16` and
+        // would panic on a naive `.abs()` — make sure it's handled cleanly.
+        assert_eq!(normalize_thumb_axis(i16::MIN, 0), 1.0);
+    }
+}